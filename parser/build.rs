@@ -0,0 +1,21 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+#[path = "src/codegen.rs"]
+mod codegen;
+
+fn main() {
+    println!("cargo:rerun-if-changed=grammar.ron");
+
+    let grammar_ron = fs::read_to_string("grammar.ron").expect("grammar.ron should read");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR should be set by cargo"));
+
+    fs::write(out_dir.join("generated.rs"), codegen::generate(&grammar_ron))
+        .expect("generated.rs should write");
+    fs::write(
+        out_dir.join("generated_ast.rs"),
+        codegen::generate_ast_nodes(&grammar_ron),
+    )
+    .expect("generated_ast.rs should write");
+}
@@ -0,0 +1,271 @@
+//! Typed wrappers over the untyped `SyntaxNode` green tree, modeled on
+//! `ra_syntax::ast`. These give tree-walking consumers (interpreter,
+//! formatter, linter) a stable API instead of matching on raw `SyntaxKind`
+//! and hardcoding child ordering.
+
+use crate::parser::{SyntaxNode, SyntaxToken};
+use crate::types::SyntaxKind;
+
+/// Implemented by every typed AST wrapper around a `SyntaxNode`.
+pub trait AstNode {
+    fn can_cast(kind: SyntaxKind) -> bool
+    where
+        Self: Sized;
+
+    fn cast(node: SyntaxNode) -> Option<Self>
+    where
+        Self: Sized;
+
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($name:ident, $kind:pat) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name(SyntaxNode);
+
+        impl AstNode for $name {
+            fn can_cast(kind: SyntaxKind) -> bool {
+                matches!(kind, $kind)
+            }
+
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                if Self::can_cast(node.kind()) {
+                    Some(Self(node))
+                } else {
+                    None
+                }
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                &self.0
+            }
+        }
+    };
+}
+
+// `Term`/`Factor`/`Comparison`/`Equality` are the four precedence levels the
+// parser emits; they all shake out to "lhs, operator, rhs", so `grammar.ron`
+// maps all four to a single `BinaryExpr` wrapper instead of four
+// near-identical types. See `codegen.rs`.
+include!(concat!(env!("OUT_DIR"), "/generated_ast.rs"));
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Literal(Literal),
+    ParenExpr(ParenExpr),
+    UnaryExpr(UnaryExpr),
+    BinaryExpr(BinaryExpr),
+}
+
+impl AstNode for Expr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        Literal::can_cast(kind)
+            || ParenExpr::can_cast(kind)
+            || UnaryExpr::can_cast(kind)
+            || BinaryExpr::can_cast(kind)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let expr = match node.kind() {
+            k if Literal::can_cast(k) => Expr::Literal(Literal(node)),
+            k if ParenExpr::can_cast(k) => Expr::ParenExpr(ParenExpr(node)),
+            k if UnaryExpr::can_cast(k) => Expr::UnaryExpr(UnaryExpr(node)),
+            k if BinaryExpr::can_cast(k) => Expr::BinaryExpr(BinaryExpr(node)),
+            _ => return None,
+        };
+        Some(expr)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Expr::Literal(it) => it.syntax(),
+            Expr::ParenExpr(it) => it.syntax(),
+            Expr::UnaryExpr(it) => it.syntax(),
+            Expr::BinaryExpr(it) => it.syntax(),
+        }
+    }
+}
+
+/// The `SyntaxKind`s that are never semantically meaningful children: they
+/// separate tokens in the source but carry no information of their own.
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Whitespace
+            | SyntaxKind::LineComment
+            | SyntaxKind::BlockComment
+            | SyntaxKind::Newline
+    )
+}
+
+fn is_operator(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Plus
+            | SyntaxKind::Minus
+            | SyntaxKind::Star
+            | SyntaxKind::Slash
+            | SyntaxKind::Greater
+            | SyntaxKind::GreaterEqual
+            | SyntaxKind::Less
+            | SyntaxKind::LessEqual
+            | SyntaxKind::BangEqual
+            | SyntaxKind::EqualEqual
+    )
+}
+
+fn children<N: AstNode>(parent: &SyntaxNode) -> impl Iterator<Item = N> {
+    parent.children().filter_map(N::cast)
+}
+
+fn non_trivia_token(
+    parent: &SyntaxNode,
+    pred: impl Fn(SyntaxKind) -> bool,
+) -> Option<SyntaxToken> {
+    parent
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|t| pred(t.kind()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    Number,
+    String,
+    Bool(bool),
+    Nil,
+}
+
+impl Literal {
+    /// The single non-trivia token this node wraps.
+    pub fn token(&self) -> SyntaxToken {
+        non_trivia_token(self.syntax(), |k| !is_trivia(k))
+            .expect("Literal node should contain exactly one non-trivia token")
+    }
+
+    pub fn kind(&self) -> LiteralKind {
+        match self.token().kind() {
+            SyntaxKind::Number => LiteralKind::Number,
+            SyntaxKind::StringLiteral => LiteralKind::String,
+            SyntaxKind::True => LiteralKind::Bool(true),
+            SyntaxKind::False => LiteralKind::Bool(false),
+            SyntaxKind::Nil => LiteralKind::Nil,
+            other => unreachable!("not a literal token kind: {other:?}"),
+        }
+    }
+}
+
+impl ParenExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        children(self.syntax()).next()
+    }
+}
+
+impl UnaryExpr {
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        non_trivia_token(self.syntax(), |k| {
+            matches!(k, SyntaxKind::Bang | SyntaxKind::Minus)
+        })
+    }
+
+    pub fn operand(&self) -> Option<Expr> {
+        children(self.syntax()).next()
+    }
+}
+
+impl BinaryExpr {
+    /// The left operand. For a chain like `a + b + c` this is itself a
+    /// `BinaryExpr` wrapping `a + b`, since the parser re-nests same-level
+    /// chains pairwise (see `bin_expr` in `parser.rs`) rather than emitting
+    /// one node per precedence level with more than two operand children.
+    pub fn lhs(&self) -> Option<Expr> {
+        children(self.syntax()).next()
+    }
+
+    /// The right operand, always the second and last operand child.
+    pub fn rhs(&self) -> Option<Expr> {
+        children(self.syntax()).nth(1)
+    }
+
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        non_trivia_token(self.syntax(), is_operator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer;
+    use crate::parser;
+    use crate::types::SyntaxKind;
+
+    /// `1+2+3` has no comparison/equality operators, so the actual fold
+    /// happens at the `Term` level; `Equality`/`Comparison` above it just
+    /// wrap a single operand with no `op_token`.
+    fn parse_expr(src: &str) -> Expr {
+        let root = parser::parse(lexer::lex(src)).syntax();
+        let term = root
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::Term)
+            .expect("root should contain a Term node");
+        Expr::cast(term).expect("Term should cast to Expr")
+    }
+
+    fn first<N: AstNode>(src: &str) -> N {
+        let root = parser::parse(lexer::lex(src)).syntax();
+        root.descendants().find_map(N::cast).unwrap_or_else(|| {
+            panic!("{src:?} should contain a node castable to the requested type")
+        })
+    }
+
+    #[test]
+    fn literal_kind_covers_every_literal_token() {
+        assert_eq!(first::<Literal>("42").kind(), LiteralKind::Number);
+        assert_eq!(first::<Literal>(r#""hi""#).kind(), LiteralKind::String);
+        assert_eq!(first::<Literal>("true").kind(), LiteralKind::Bool(true));
+        assert_eq!(first::<Literal>("false").kind(), LiteralKind::Bool(false));
+        assert_eq!(first::<Literal>("nil").kind(), LiteralKind::Nil);
+    }
+
+    #[test]
+    fn paren_expr_unwraps_its_inner_expression() {
+        // The inner expression is a bare `42`, but the parser still wraps it
+        // in the `Equality`/`Comparison`/`Term`/`Factor` levels that
+        // `BinaryExpr` casts from (see `grammar.ron`), so `expr()` returns
+        // the outermost of those wrappers. Checking the text proves it is
+        // `Some` and covers the inner `42`.
+        let paren = first::<ParenExpr>("(42)");
+        assert_eq!(paren.expr().unwrap().syntax().text().to_string(), "42");
+    }
+
+    #[test]
+    fn unary_expr_exposes_its_operator_and_operand() {
+        let unary = first::<UnaryExpr>("-42");
+        assert_eq!(unary.op_token().unwrap().text(), "-");
+        assert_eq!(unary.operand().unwrap().syntax().text().to_string(), "42");
+
+        let not_expr = first::<UnaryExpr>("!true");
+        assert_eq!(not_expr.op_token().unwrap().text(), "!");
+        assert_eq!(not_expr.operand().unwrap().syntax().text().to_string(), "true");
+    }
+
+    /// `1+2+3` walks as `((1+2)+3)`: `rhs()` on the outer node covers `3`,
+    /// and `lhs()` is itself a `BinaryExpr` whose own `rhs()` covers `2`,
+    /// so no operand is silently dropped.
+    #[test]
+    fn binary_expr_walks_a_three_operand_chain_without_dropping_data() {
+        let Expr::BinaryExpr(outer) = parse_expr("1+2+3") else {
+            panic!("expected a BinaryExpr");
+        };
+        assert_eq!(outer.op_token().unwrap().text(), "+");
+        assert_eq!(outer.rhs().unwrap().syntax().text().to_string(), "3");
+
+        let Some(Expr::BinaryExpr(lhs)) = outer.lhs() else {
+            panic!("expected a nested BinaryExpr lhs");
+        };
+        assert_eq!(lhs.op_token().unwrap().text(), "+");
+        assert_eq!(lhs.rhs().unwrap().syntax().text().to_string(), "2");
+        assert_eq!(lhs.lhs().unwrap().syntax().text().to_string(), "1");
+    }
+}
@@ -0,0 +1,160 @@
+//! Turns `grammar.ron` into the `TokenKind`/`SyntaxKind` enums, the
+//! `From<TokenKind> for SyntaxKind` impl, the keyword `phf::Map`, and the
+//! `ast_node!` invocations that back the typed AST layer.
+//!
+//! This file is compiled twice, never as part of the library itself: once
+//! by `build.rs` (via `#[path]`, to actually run the generator) and once by
+//! `cargo test` (via the `#[cfg(test)] mod codegen;` in `lib.rs`, to check
+//! the generator is deterministic and that `grammar.ron` hasn't drifted
+//! from what's committed).
+
+#[derive(serde::Deserialize)]
+pub struct Grammar {
+    punctuation: Vec<(String, String)>,
+    literals: Vec<String>,
+    keywords: Vec<String>,
+    trivia: Vec<String>,
+    errors: Vec<String>,
+    nodes: Vec<NodeEntry>,
+    syntax_only: Vec<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct NodeEntry {
+    kind: String,
+    ast: String,
+}
+
+fn titlecase(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+fn token_variants(grammar: &Grammar) -> Vec<String> {
+    grammar
+        .punctuation
+        .iter()
+        .map(|(_, name)| name.clone())
+        .chain(grammar.literals.iter().cloned())
+        .chain(grammar.keywords.iter().map(|kw| titlecase(kw)))
+        .chain(grammar.trivia.iter().cloned())
+        .chain(grammar.errors.iter().cloned())
+        .collect()
+}
+
+/// Generate `TokenKind`, `SyntaxKind`, their conversion impl, and the
+/// keyword map. Written to `$OUT_DIR/generated.rs` and `include!`d from
+/// `types.rs`.
+pub fn generate(grammar_ron: &str) -> String {
+    let grammar: Grammar = ron::from_str(grammar_ron).expect("grammar.ron should parse");
+    let tokens = token_variants(&grammar);
+
+    let mut node_only: Vec<String> = Vec::new();
+    for entry in &grammar.nodes {
+        if !node_only.contains(&entry.kind) {
+            node_only.push(entry.kind.clone());
+        }
+    }
+    node_only.extend(grammar.syntax_only.iter().cloned());
+
+    let mut out = String::new();
+    out.push_str("// @generated from grammar.ron by build.rs -- do not edit by hand.\n\n");
+
+    out.push_str("#[derive(Clone, Debug, Copy, PartialEq, Eq)]\npub enum TokenKind {\n");
+    for variant in &tokens {
+        out.push_str(&format!("    {variant},\n"));
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]\n#[repr(u16)]\npub enum SyntaxKind {\n");
+    for variant in &tokens {
+        out.push_str(&format!("    {variant},\n"));
+    }
+    for variant in &node_only {
+        out.push_str(&format!("    {variant},\n"));
+    }
+    out.push_str("    Root, // must stay last; rowan's bounds check relies on it\n}\n\n");
+
+    out.push_str("impl From<TokenKind> for SyntaxKind {\n");
+    out.push_str("    fn from(value: TokenKind) -> Self {\n        match value {\n");
+    for variant in &tokens {
+        out.push_str(&format!(
+            "            TokenKind::{variant} => SyntaxKind::{variant},\n"
+        ));
+    }
+    out.push_str("        }\n    }\n}\n\n");
+
+    out.push_str("pub(crate) static KEYWORDS: phf::Map<&'static str, TokenKind> = phf::phf_map! {\n");
+    for keyword in &grammar.keywords {
+        out.push_str(&format!(
+            "    \"{keyword}\" => TokenKind::{},\n",
+            titlecase(keyword)
+        ));
+    }
+    out.push_str("};\n");
+
+    out
+}
+
+/// Generate the `ast_node!(Name, SyntaxKind::A | SyntaxKind::B | ...)`
+/// invocations, one per distinct `ast` name in `grammar.ron`'s `nodes`
+/// table, in first-seen order. Written to `$OUT_DIR/generated_ast.rs` and
+/// `include!`d from `ast.rs`, right after the `ast_node!` macro definition.
+pub fn generate_ast_nodes(grammar_ron: &str) -> String {
+    let grammar: Grammar = ron::from_str(grammar_ron).expect("grammar.ron should parse");
+
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for entry in &grammar.nodes {
+        match groups.iter_mut().find(|(name, _)| *name == entry.ast) {
+            Some((_, kinds)) => kinds.push(entry.kind.clone()),
+            None => groups.push((entry.ast.clone(), vec![entry.kind.clone()])),
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("// @generated from grammar.ron by build.rs -- do not edit by hand.\n\n");
+    for (ast_name, kinds) in groups {
+        let pattern = kinds
+            .iter()
+            .map(|kind| format!("SyntaxKind::{kind}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        out.push_str(&format!("ast_node!({ast_name}, {pattern});\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR_RON: &str = include_str!("../grammar.ron");
+
+    // Mirrors rowan's `tidy.rs`: re-run the generator and diff it against
+    // what's checked in, so a `grammar.ron` edit that forgets to update the
+    // committed snapshot fails CI instead of silently drifting.
+    #[test]
+    fn generated_kinds_snapshot_is_up_to_date() {
+        let expected = include_str!("generated_snapshot.rs");
+        assert_eq!(
+            generate(GRAMMAR_RON),
+            expected,
+            "generated_snapshot.rs is stale -- re-run `cargo build`, copy \
+             $OUT_DIR/generated.rs over src/generated_snapshot.rs, and commit it"
+        );
+    }
+
+    #[test]
+    fn generated_ast_nodes_snapshot_is_up_to_date() {
+        let expected = include_str!("generated_ast_snapshot.rs");
+        assert_eq!(
+            generate_ast_nodes(GRAMMAR_RON),
+            expected,
+            "generated_ast_snapshot.rs is stale -- re-run `cargo build`, copy \
+             $OUT_DIR/generated_ast.rs over src/generated_ast_snapshot.rs, and commit it"
+        );
+    }
+}
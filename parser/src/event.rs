@@ -0,0 +1,266 @@
+//! Event-based parsing infrastructure, modeled on rust-analyzer's
+//! `parser_api`/`parser_impl` split.
+//!
+//! Instead of writing straight into a `GreenNodeBuilder`, the grammar in
+//! `parser.rs` records a flat `Vec<Event>` as it goes. This lets a node be
+//! wrapped in a new parent *after* it has already been completed (see
+//! [`CompletedMarker::precede`]), which a direct builder can't do, and keeps
+//! error recovery a matter of pushing more events instead of unwinding calls.
+//!
+//! A [`TreeSink`] pass at the end replays the events into a real
+//! `GreenNodeBuilder`, resolving `forward_parent` chains along the way.
+
+use std::mem;
+
+use rowan::GreenNodeBuilder;
+
+use crate::lexer;
+use crate::types::{Diagnostic, SyntaxKind, TokenKind};
+
+#[derive(Debug)]
+pub enum Event {
+    /// Like `GreenNodeBuilder::start_node`, but may be retargeted later: if
+    /// `forward_parent` is set, the *real* parent is the `Start` event
+    /// `forward_parent` slots ahead of this one, and this node nests inside
+    /// it once the sink walks the chain.
+    Start {
+        kind: SyntaxKind,
+        forward_parent: Option<usize>,
+    },
+    Finish,
+    Token {
+        kind: SyntaxKind,
+        text: String,
+    },
+    Error(Diagnostic),
+}
+
+impl Event {
+    fn tombstone() -> Event {
+        Event::Start {
+            kind: SyntaxKind::Tombstone,
+            forward_parent: None,
+        }
+    }
+}
+
+/// A placeholder for a node that has been opened but not yet completed.
+pub struct Marker {
+    pos: usize,
+}
+
+impl Marker {
+    fn new(pos: usize) -> Marker {
+        Marker { pos }
+    }
+
+    /// Fill in the node's real kind and close it, producing a handle that
+    /// can still be wrapped in a further parent via `precede`.
+    pub fn complete(self, p: &mut Parser, kind: SyntaxKind) -> CompletedMarker {
+        match &mut p.events[self.pos] {
+            Event::Start { kind: slot, .. } => *slot = kind,
+            _ => unreachable!(),
+        }
+        p.events.push(Event::Finish);
+        CompletedMarker::new(self.pos, kind)
+    }
+
+    /// Drop this marker without emitting a node. Only valid immediately
+    /// after `start()`, before any other event was pushed.
+    pub fn abandon(self, p: &mut Parser) {
+        if self.pos == p.events.len() - 1 {
+            match p.events.pop() {
+                Some(Event::Start {
+                    kind: SyntaxKind::Tombstone,
+                    forward_parent: None,
+                }) => (),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+/// A node that has already been closed, but can still become the child of a
+/// node opened afterwards (see `precede`).
+pub struct CompletedMarker {
+    pos: usize,
+    kind: SyntaxKind,
+}
+
+impl CompletedMarker {
+    fn new(pos: usize, kind: SyntaxKind) -> Self {
+        CompletedMarker { pos, kind }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Open a new marker that will become this node's parent once both are
+    /// completed, even though its `Start` event is emitted after this
+    /// node's own. This is how a left-associative chain like `a + b + c`
+    /// gets re-nested as `((a + b) + c)` after the fact, and how a
+    /// mis-parsed expression can be wrapped in an error node in hindsight.
+    pub fn precede(self, p: &mut Parser) -> Marker {
+        let new_marker = p.start();
+        match &mut p.events[self.pos] {
+            Event::Start { forward_parent, .. } => {
+                *forward_parent = Some(new_marker.pos - self.pos);
+            }
+            _ => unreachable!(),
+        }
+        new_marker
+    }
+}
+
+/// Drives the lexer output through the grammar, accumulating `Event`s.
+pub struct Parser {
+    tokens: Vec<lexer::Token>,
+    events: Vec<Event>,
+}
+
+impl Parser {
+    pub fn new(mut tokens: Vec<lexer::Token>) -> Parser {
+        tokens.reverse();
+        Parser {
+            tokens,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn start(&mut self) -> Marker {
+        let pos = self.events.len();
+        self.events.push(Event::tombstone());
+        Marker::new(pos)
+    }
+
+    pub fn current(&self) -> Option<TokenKind> {
+        self.tokens.last().map(|t| t.kind())
+    }
+
+    pub fn bump(&mut self) {
+        let tok = self.tokens.pop().expect("bump called with no tokens left");
+        self.events.push(Event::Token {
+            kind: SyntaxKind::from(tok.kind()),
+            text: tok.text().to_string(),
+        });
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.events.push(Event::Error(Diagnostic::new(message)));
+    }
+
+    /// Open an `ErrorUnexpected` node, consume tokens up to (but not
+    /// including) the next token in `recovery` or EOF, and close the node.
+    /// This is what lets the parser keep going and collect more than one
+    /// error instead of bailing out on the first unexpected token.
+    pub fn err_recover(&mut self, message: impl Into<String>, recovery: &[TokenKind]) {
+        self.error(message);
+        let m = self.start();
+        while let Some(kind) = self.current() {
+            if recovery.contains(&kind) {
+                break;
+            }
+            self.bump();
+        }
+        m.complete(self, SyntaxKind::ErrorUnexpected);
+    }
+
+    pub fn finish(self) -> Vec<Event> {
+        self.events
+    }
+}
+
+/// Replay a flat event stream into a `GreenNodeBuilder`, returning the
+/// collected errors. Resolves `forward_parent` chains by walking from each
+/// `Start` event to the `Start` event(s) that should actually open before
+/// it, outermost first.
+pub fn process(builder: &mut GreenNodeBuilder<'static>, mut events: Vec<Event>) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+
+    for i in 0..events.len() {
+        match mem::replace(&mut events[i], Event::tombstone()) {
+            Event::Start {
+                kind,
+                forward_parent,
+            } => {
+                // Nothing to open: this slot was already consumed as part of
+                // an earlier node's forward_parent chain.
+                if kind == SyntaxKind::Tombstone && forward_parent.is_none() {
+                    continue;
+                }
+
+                // Collect every ancestor this node should open under,
+                // innermost (this node) first.
+                let mut kinds = vec![kind];
+                let mut idx = i;
+                let mut next = forward_parent;
+                while let Some(offset) = next {
+                    idx += offset;
+                    next = match mem::replace(&mut events[idx], Event::tombstone()) {
+                        Event::Start {
+                            kind,
+                            forward_parent,
+                        } => {
+                            kinds.push(kind);
+                            forward_parent
+                        }
+                        _ => unreachable!(),
+                    };
+                }
+
+                for kind in kinds.into_iter().rev() {
+                    builder.start_node(kind.into());
+                }
+            }
+            Event::Finish => builder.finish_node(),
+            Event::Token { kind, text } => builder.token(kind.into(), &text),
+            Event::Error(message) => errors.push(message),
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SyntaxNode;
+
+    /// Hand-drive the marker API the way `bin_expr` does: complete a `lhs`
+    /// node, then retroactively wrap it in a new parent via `precede`, the
+    /// way a left-associative chain gets re-nested after the fact.
+    #[test]
+    fn precede_wraps_a_completed_marker_in_a_new_parent() {
+        let mut p = Parser::new(Vec::new());
+
+        let lhs = p.start();
+        p.events.push(Event::Token {
+            kind: SyntaxKind::Number,
+            text: "1".to_string(),
+        });
+        let lhs = lhs.complete(&mut p, SyntaxKind::Literal);
+
+        let outer = lhs.precede(&mut p);
+        p.events.push(Event::Token {
+            kind: SyntaxKind::Plus,
+            text: "+".to_string(),
+        });
+        p.events.push(Event::Token {
+            kind: SyntaxKind::Number,
+            text: "2".to_string(),
+        });
+        outer.complete(&mut p, SyntaxKind::Term);
+
+        let mut builder = GreenNodeBuilder::new();
+        let errors = process(&mut builder, p.finish());
+        assert!(errors.is_empty());
+
+        let root = SyntaxNode::new_root(builder.finish());
+        assert_eq!(root.kind(), SyntaxKind::Term);
+        let mut children = root.children();
+        let inner = children.next().expect("Term should wrap the Literal");
+        assert_eq!(inner.kind(), SyntaxKind::Literal);
+        assert!(children.next().is_none(), "Term should have exactly one child node");
+    }
+}
@@ -0,0 +1,6 @@
+// @generated from grammar.ron by build.rs -- do not edit by hand.
+
+ast_node!(Literal, SyntaxKind::Literal);
+ast_node!(ParenExpr, SyntaxKind::ParenExpr);
+ast_node!(UnaryExpr, SyntaxKind::Unary);
+ast_node!(BinaryExpr, SyntaxKind::Factor | SyntaxKind::Term | SyntaxKind::Comparison | SyntaxKind::Equality);
@@ -0,0 +1,177 @@
+// @generated from grammar.ron by build.rs -- do not edit by hand.
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    LParen,
+    RParen,
+    LBrac,
+    RBrac,
+    Comma,
+    Dot,
+    Minus,
+    Plus,
+    Semicolon,
+    Slash,
+    Star,
+    Bang,
+    BangEqual,
+    Equal,
+    EqualEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Identifier,
+    StringLiteral,
+    Number,
+    And,
+    Class,
+    Else,
+    False,
+    Fn,
+    For,
+    If,
+    Nil,
+    Or,
+    Print,
+    Return,
+    Super,
+    This,
+    True,
+    Var,
+    While,
+    LineComment,
+    BlockComment,
+    Whitespace,
+    Newline,
+    ErrorUnexpected,
+    ErrorUnterminatedString,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u16)]
+pub enum SyntaxKind {
+    LParen,
+    RParen,
+    LBrac,
+    RBrac,
+    Comma,
+    Dot,
+    Minus,
+    Plus,
+    Semicolon,
+    Slash,
+    Star,
+    Bang,
+    BangEqual,
+    Equal,
+    EqualEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Identifier,
+    StringLiteral,
+    Number,
+    And,
+    Class,
+    Else,
+    False,
+    Fn,
+    For,
+    If,
+    Nil,
+    Or,
+    Print,
+    Return,
+    Super,
+    This,
+    True,
+    Var,
+    While,
+    LineComment,
+    BlockComment,
+    Whitespace,
+    Newline,
+    ErrorUnexpected,
+    ErrorUnterminatedString,
+    Literal,
+    ParenExpr,
+    Unary,
+    Factor,
+    Term,
+    Comparison,
+    Equality,
+    Tombstone,
+    Root, // must stay last; rowan's bounds check relies on it
+}
+
+impl From<TokenKind> for SyntaxKind {
+    fn from(value: TokenKind) -> Self {
+        match value {
+            TokenKind::LParen => SyntaxKind::LParen,
+            TokenKind::RParen => SyntaxKind::RParen,
+            TokenKind::LBrac => SyntaxKind::LBrac,
+            TokenKind::RBrac => SyntaxKind::RBrac,
+            TokenKind::Comma => SyntaxKind::Comma,
+            TokenKind::Dot => SyntaxKind::Dot,
+            TokenKind::Minus => SyntaxKind::Minus,
+            TokenKind::Plus => SyntaxKind::Plus,
+            TokenKind::Semicolon => SyntaxKind::Semicolon,
+            TokenKind::Slash => SyntaxKind::Slash,
+            TokenKind::Star => SyntaxKind::Star,
+            TokenKind::Bang => SyntaxKind::Bang,
+            TokenKind::BangEqual => SyntaxKind::BangEqual,
+            TokenKind::Equal => SyntaxKind::Equal,
+            TokenKind::EqualEqual => SyntaxKind::EqualEqual,
+            TokenKind::Greater => SyntaxKind::Greater,
+            TokenKind::GreaterEqual => SyntaxKind::GreaterEqual,
+            TokenKind::Less => SyntaxKind::Less,
+            TokenKind::LessEqual => SyntaxKind::LessEqual,
+            TokenKind::Identifier => SyntaxKind::Identifier,
+            TokenKind::StringLiteral => SyntaxKind::StringLiteral,
+            TokenKind::Number => SyntaxKind::Number,
+            TokenKind::And => SyntaxKind::And,
+            TokenKind::Class => SyntaxKind::Class,
+            TokenKind::Else => SyntaxKind::Else,
+            TokenKind::False => SyntaxKind::False,
+            TokenKind::Fn => SyntaxKind::Fn,
+            TokenKind::For => SyntaxKind::For,
+            TokenKind::If => SyntaxKind::If,
+            TokenKind::Nil => SyntaxKind::Nil,
+            TokenKind::Or => SyntaxKind::Or,
+            TokenKind::Print => SyntaxKind::Print,
+            TokenKind::Return => SyntaxKind::Return,
+            TokenKind::Super => SyntaxKind::Super,
+            TokenKind::This => SyntaxKind::This,
+            TokenKind::True => SyntaxKind::True,
+            TokenKind::Var => SyntaxKind::Var,
+            TokenKind::While => SyntaxKind::While,
+            TokenKind::LineComment => SyntaxKind::LineComment,
+            TokenKind::BlockComment => SyntaxKind::BlockComment,
+            TokenKind::Whitespace => SyntaxKind::Whitespace,
+            TokenKind::Newline => SyntaxKind::Newline,
+            TokenKind::ErrorUnexpected => SyntaxKind::ErrorUnexpected,
+            TokenKind::ErrorUnterminatedString => SyntaxKind::ErrorUnterminatedString,
+        }
+    }
+}
+
+pub(crate) static KEYWORDS: phf::Map<&'static str, TokenKind> = phf::phf_map! {
+    "and" => TokenKind::And,
+    "class" => TokenKind::Class,
+    "else" => TokenKind::Else,
+    "false" => TokenKind::False,
+    "fn" => TokenKind::Fn,
+    "for" => TokenKind::For,
+    "if" => TokenKind::If,
+    "nil" => TokenKind::Nil,
+    "or" => TokenKind::Or,
+    "print" => TokenKind::Print,
+    "return" => TokenKind::Return,
+    "super" => TokenKind::Super,
+    "this" => TokenKind::This,
+    "true" => TokenKind::True,
+    "var" => TokenKind::Var,
+    "while" => TokenKind::While,
+};
@@ -1,59 +1,4 @@
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
-pub enum TokenKind {
-    // single character
-    LParen,
-    RParen,
-    LBrac,
-    RBrac,
-    Comma,
-    Dot,
-    Minus,
-    Plus,
-    Semicolon,
-    Slash,
-    Star,
-
-    // one or two character
-    Bang,
-    BangEqual,
-    Equal,
-    EqualEqual,
-    Greater,
-    GreaterEqual,
-    Less,
-    LessEqual,
-
-    // Literals
-    Identifier,
-    StringLiteral, // don't clobber builtin "String"
-    Number,
-
-    // Keywords
-    And,
-    Class,
-    Else,
-    False,
-    Fn,
-    For,
-    If,
-    Nil,
-    Or,
-    Print,
-    Return,
-    Super,
-    This,
-    True,
-    Var,
-    While,
-
-    LineComment,
-    BlockComment,
-    Whitespace,
-    Newline,
-
-    ErrorUnexpected,
-    ErrorUnterminatedString,
-}
+use crate::types::{TokenKind, KEYWORDS};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Token {
@@ -61,6 +6,16 @@ pub struct Token {
     text: String,
 }
 
+impl Token {
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
 pub fn lex(input: &str) -> Vec<Token> {
     let mut res = Vec::new();
     let mut rest = input;
@@ -76,25 +31,6 @@ pub fn lex(input: &str) -> Vec<Token> {
     res
 }
 
-static KEYWORDS: phf::Map<&'static str, TokenKind> = phf::phf_map! {
-    "and" => TokenKind::And,
-    "class" => TokenKind::Class,
-    "else" => TokenKind::Else,
-    "false" => TokenKind::False,
-    "for" => TokenKind::For,
-    "fn" => TokenKind::Fn,
-    "if" => TokenKind::If,
-    "nil" => TokenKind::Nil,
-    "or" => TokenKind::Or,
-    "print" => TokenKind::Print,
-    "return" => TokenKind::Return,
-    "super" => TokenKind::Super,
-    "this" => TokenKind::This,
-    "true" => TokenKind::True,
-    "var" => TokenKind::Var,
-    "while" => TokenKind::While,
-};
-
 fn valid_token(input: &str) -> Option<Token> {
     if input.is_empty() {
         return None;
@@ -198,6 +134,19 @@ fn valid_token(input: &str) -> Option<Token> {
                     return Some(Token { kind: TokenKind::ErrorUnterminatedString, text });
                 };
                 text.push(c);
+                if c == '\\' {
+                    // An escaped character never ends the literal, even if
+                    // it's a `"` (`\"`); consume it verbatim and keep
+                    // scanning. Deciding whether the escape itself is valid
+                    // (`\xHH`, `\u{...}`, ...) is the validation pass's job,
+                    // not the lexer's -- the token text must stay a
+                    // byte-for-byte copy of the source either way.
+                    let Some(escaped) = chars.next() else {
+                        return Some(Token { kind: TokenKind::ErrorUnterminatedString, text });
+                    };
+                    text.push(escaped);
+                    continue;
+                }
                 if c == '"' {
                     break;
                 }
@@ -211,10 +160,36 @@ fn valid_token(input: &str) -> Option<Token> {
         c if c.is_numeric() => {
             let mut text = String::new();
             text.push(c);
-            let mut c: Option<char>;
+
+            // `0x`/`0b`/`0o` switch to a different digit alphabet entirely;
+            // none of the float machinery below (`.`, exponents) applies.
+            if c == '0' {
+                if let Some(radix_char) = chars.peek().copied().filter(|c| matches!(c, 'x' | 'b' | 'o'))
+                {
+                    text.push(radix_char);
+                    chars.next();
+                    let is_radix_digit: fn(char) -> bool = match radix_char {
+                        'x' => |c| c.is_ascii_hexdigit() || c == '_',
+                        'b' => |c| matches!(c, '0' | '1' | '_'),
+                        _ => |c| matches!(c, '0'..='7' | '_'),
+                    };
+                    for c in chars.by_ref() {
+                        if !is_radix_digit(c) {
+                            break;
+                        }
+                        text.push(c);
+                    }
+                    return Some(Token {
+                        kind: TokenKind::Number,
+                        text,
+                    });
+                }
+            }
+
+            let mut last: Option<char>;
             loop {
-                c = chars.next();
-                let Some(c) = c else {
+                last = chars.next();
+                let Some(c) = last else {
                     break;
                 };
                 if !c.is_numeric() && c != '_' {
@@ -223,18 +198,51 @@ fn valid_token(input: &str) -> Option<Token> {
                 text.push(c);
             }
 
-            if c.is_some()
-                && c.unwrap() == '.'
-                && chars.peek().map(|c| c.is_numeric()).unwrap_or(false)
-            {
+            // Keep absorbing further `.digits` groups too, not just the
+            // first: a malformed literal like `1.2.3` should still lex as
+            // one `Number` token, so `validate_number`'s "more than one
+            // `.`" check can catch it, instead of splitting into
+            // `Number("1.2")`, `Dot`, `Number("3")` with no diagnostic at
+            // all for the stray `.3`.
+            while last == Some('.') && chars.peek().map(|c| c.is_numeric()).unwrap_or(false) {
                 text.push('.');
-                for c in chars.by_ref() {
+                loop {
+                    last = chars.next();
+                    let Some(c) = last else {
+                        break;
+                    };
                     if !c.is_numeric() && c != '_' {
                         break;
                     }
                     text.push(c);
                 }
             }
+
+            // An exponent is only consumed once we know it has at least one
+            // digit or `_` (so `1e_` is consumed and left to validation to
+            // reject), so `1e` with neither after it is left as-is (the
+            // `e` starts a fresh identifier token instead).
+            if matches!(last, Some('e' | 'E')) {
+                let mut lookahead = chars.clone();
+                let mut suffix = String::new();
+                suffix.push(last.unwrap());
+                if let Some(sign @ ('+' | '-')) = lookahead.peek().copied() {
+                    suffix.push(sign);
+                    lookahead.next();
+                }
+                let digits_start = suffix.len();
+                while let Some(d) = lookahead.peek().copied() {
+                    if !d.is_numeric() && d != '_' {
+                        break;
+                    }
+                    suffix.push(d);
+                    lookahead.next();
+                }
+                if suffix.len() > digits_start {
+                    text.push_str(&suffix);
+                }
+            }
+
             return Some(Token {
                 kind: TokenKind::Number,
                 text,
@@ -273,3 +281,52 @@ fn invalid_token(input: &str) -> Token {
         text: input[..len].into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_second_dot_stays_in_one_number_token() {
+        let tokens = lex("1.2.3");
+        assert_eq!(tokens.len(), 1, "1.2.3 should lex as a single token");
+        assert_eq!(tokens[0].kind(), TokenKind::Number);
+        assert_eq!(tokens[0].text(), "1.2.3");
+    }
+
+    #[test]
+    fn ordinary_float_still_lexes_as_one_number_token() {
+        let tokens = lex("1.5");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind(), TokenKind::Number);
+        assert_eq!(tokens[0].text(), "1.5");
+    }
+
+    #[test]
+    fn a_dot_not_followed_by_a_digit_is_left_as_its_own_token() {
+        let tokens = lex("1.foo");
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].kind(), TokenKind::Number);
+        assert_eq!(tokens[0].text(), "1");
+        assert_eq!(tokens[1].kind(), TokenKind::Dot);
+        assert_eq!(tokens[2].kind(), TokenKind::Identifier);
+    }
+
+    #[test]
+    fn an_exponent_with_digits_is_consumed_into_the_number_token() {
+        let tokens = lex("1e10");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind(), TokenKind::Number);
+        assert_eq!(tokens[0].text(), "1e10");
+    }
+
+    #[test]
+    fn a_bare_trailing_e_without_digits_starts_a_fresh_identifier() {
+        let tokens = lex("1e");
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].kind(), TokenKind::Number);
+        assert_eq!(tokens[0].text(), "1");
+        assert_eq!(tokens[1].kind(), TokenKind::Identifier);
+        assert_eq!(tokens[1].text(), "e");
+    }
+}
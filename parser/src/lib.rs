@@ -0,0 +1,11 @@
+pub mod ast;
+// Only needed to exercise the generator's up-to-date check against
+// `grammar.ron`; `build.rs` compiles this same file independently (via
+// `#[path]`) to actually run it.
+#[cfg(test)]
+mod codegen;
+pub mod event;
+pub mod lexer;
+pub mod parser;
+pub mod types;
+pub mod validation;
@@ -1,6 +1,9 @@
+use crate::event::{self, Parser};
 use crate::lexer;
+use crate::types::Diagnostic;
 use crate::types::SyntaxKind;
 use crate::types::TokenKind;
+use crate::validation;
 
 // Some boilerplate is needed, as rowan settled on using its own
 // `struct SyntaxKind(u16)` internally, instead of accepting the
@@ -47,193 +50,387 @@ use rowan::GreenNodeBuilder;
 pub struct Parse {
     green_node: GreenNode,
 
-    #[allow(unused)]
-    pub errors: Vec<String>,
+    pub errors: Vec<Diagnostic>,
 }
 
-pub fn parse(mut tokens: Vec<lexer::Token>) -> Parse {
-    struct Parser {
-        /// input tokens, including whitespace,
-        /// in *reverse* order.
-        tokens: Vec<lexer::Token>,
-        /// the in-progress tree.
-        builder: GreenNodeBuilder<'static>,
-        /// the list of syntax errors we've accumulated
-        /// so far.
-        errors: Vec<String>,
+/// Tokens the recovery pass can resynchronize on: wherever one of these
+/// shows up, it's a reasonable place to stop swallowing unexpected input and
+/// let the caller's grammar rule carry on.
+const RECOVERY_SET: &[TokenKind] = &[TokenKind::Semicolon, TokenKind::RParen, TokenKind::Newline];
+
+fn skip_ws(p: &mut Parser) {
+    while p.current() == Some(TokenKind::Whitespace) {
+        p.bump();
     }
+}
 
-    impl Parser {
-        /// Advance one token, adding it to the current branch of the tree builder.
-        fn bump(&mut self) {
-            let tok = self.tokens.pop().unwrap();
-            self.builder
-                .token(SyntaxKind::from(tok.kind).into(), tok.text.as_str());
-        }
-        /// Peek at the first unprocessed token
-        fn current(&self) -> Option<TokenKind> {
-            self.tokens.last().map(|t| t.kind)
+fn primary(p: &mut Parser) {
+    skip_ws(p);
+
+    match p.current() {
+        Some(
+            TokenKind::False
+            | TokenKind::True
+            | TokenKind::Nil
+            | TokenKind::Number
+            | TokenKind::StringLiteral,
+        ) => {
+            let m = p.start();
+            p.bump();
+            m.complete(p, SyntaxKind::Literal);
         }
-        fn skip_ws(&mut self) {
-            while self.current() == Some(TokenKind::Whitespace) {
-                self.bump()
+        Some(TokenKind::LParen) => {
+            let m = p.start();
+            p.bump();
+            expression(p);
+            match p.current() {
+                Some(TokenKind::RParen) => p.bump(),
+                Some(_) => p.err_recover("expected ')'", RECOVERY_SET),
+                None => p.error("unexpected EOF"),
             }
+            m.complete(p, SyntaxKind::ParenExpr);
         }
+        Some(_) => p.err_recover("unexpected token", RECOVERY_SET),
+        None => p.error("unexpected EOF"),
+    }
+}
 
-        fn unexpected(&mut self) {
-            self.builder.start_node(SyntaxKind::ErrorUnexpected.into());
-            self.errors.push("Unexpected token".into());
-            self.bump();
-            self.builder.finish_node();
-        }
-        fn unexpected_eof(&mut self) {
-            self.errors.push("Unexpected EOF".into());
-        }
+fn unary(p: &mut Parser) {
+    skip_ws(p);
 
-        fn primary(&mut self) {
-            self.skip_ws();
-
-            match self.current() {
-                Some(
-                    TokenKind::False
-                    | TokenKind::True
-                    | TokenKind::Nil
-                    | TokenKind::Number
-                    | TokenKind::StringLiteral,
-                ) => {
-                    self.bump();
-                }
-                Some(TokenKind::LParen) => {
-                    self.bump();
-                    self.expression();
-                    match self.current() {
-                        Some(TokenKind::RParen) => self.bump(),
-                        Some(_) => self.unexpected(),
-                        None => self.unexpected_eof(),
-                    }
-                }
-                Some(_) => self.unexpected(),
-                None => self.unexpected_eof(),
-            }
-        }
+    if matches!(p.current(), Some(TokenKind::Bang | TokenKind::Minus)) {
+        let m = p.start();
+        p.bump();
+        unary(p);
+        m.complete(p, SyntaxKind::Unary);
+        return;
+    }
 
-        fn unary(&mut self) {
-            self.skip_ws();
+    primary(p);
+}
 
-            if matches!(self.current(), Some(TokenKind::Bang | TokenKind::Minus)) {
-                self.builder.start_node(SyntaxKind::Unary.into());
-                self.bump();
-                self.unary();
-                return;
-            }
+/// A left-associative binary precedence level: parse one `operand`, then
+/// keep folding in more of them as long as the current token is one of
+/// `ops`, re-nesting each fold via `precede` so a chain like `a + b + c`
+/// comes out as `((a + b) + c)` instead of one flat node with three
+/// operands and two operators.
+fn bin_expr(p: &mut Parser, kind: SyntaxKind, ops: &[TokenKind], operand: fn(&mut Parser)) {
+    let m = p.start();
+    operand(p);
+    let mut lhs = m.complete(p, kind);
+
+    while matches!(p.current(), Some(op) if ops.contains(&op)) {
+        let m = lhs.precede(p);
+        p.bump();
+        operand(p);
+        lhs = m.complete(p, kind);
+    }
+}
 
-            self.primary();
-        }
+fn factor(p: &mut Parser) {
+    bin_expr(p, SyntaxKind::Factor, &[TokenKind::Slash, TokenKind::Star], unary);
+}
 
-        fn factor(&mut self) {
-            self.builder.start_node(SyntaxKind::Factor.into());
-            self.unary();
+fn term(p: &mut Parser) {
+    bin_expr(p, SyntaxKind::Term, &[TokenKind::Minus, TokenKind::Plus], factor);
+}
 
-            while matches!(self.current(), Some(TokenKind::Slash | TokenKind::Star)) {
-                self.bump();
-                self.unary();
-            }
-            self.builder.finish_node();
-        }
+fn comparison(p: &mut Parser) {
+    bin_expr(
+        p,
+        SyntaxKind::Comparison,
+        &[
+            TokenKind::Greater,
+            TokenKind::GreaterEqual,
+            TokenKind::Less,
+            TokenKind::LessEqual,
+        ],
+        term,
+    );
+}
 
-        fn term(&mut self) {
-            self.builder.start_node(SyntaxKind::Term.into());
-            self.factor();
+fn equality(p: &mut Parser) {
+    let m = p.start();
+    comparison(p);
+    let mut lhs = m.complete(p, SyntaxKind::Equality);
+
+    skip_ws(p);
+    while matches!(p.current(), Some(TokenKind::BangEqual | TokenKind::EqualEqual)) {
+        let m = lhs.precede(p);
+        p.bump();
+        comparison(p);
+        lhs = m.complete(p, SyntaxKind::Equality);
+    }
+}
 
-            while matches!(self.current(), Some(TokenKind::Minus | TokenKind::Plus)) {
-                self.bump();
-                self.factor();
-            }
-            self.builder.finish_node();
-        }
+fn expression(p: &mut Parser) {
+    skip_ws(p);
+    equality(p);
+}
 
-        fn comparison(&mut self) {
-            self.builder.start_node(SyntaxKind::Comparison.into());
-            self.term();
-
-            while matches!(
-                self.current(),
-                Some(
-                    TokenKind::Greater
-                        | TokenKind::GreaterEqual
-                        | TokenKind::Less
-                        | TokenKind::LessEqual
-                )
-            ) {
-                self.bump();
-                self.term();
-            }
-            self.builder.finish_node();
+fn root(p: &mut Parser) {
+    let m = p.start();
+    expression(p);
+    skip_ws(p);
+
+    while p.current() == Some(TokenKind::Newline) {
+        p.bump();
+    }
+
+    if p.current().is_some() {
+        let trailing = p.start();
+        while p.current().is_some() {
+            p.bump();
         }
+        p.error("Expected EOF");
+        trailing.complete(p, SyntaxKind::ErrorUnexpected);
+    }
+    m.complete(p, SyntaxKind::Root);
+}
 
-        fn equality(&mut self) {
-            self.builder.start_node(SyntaxKind::Equality.into());
-            self.comparison();
-
-            self.skip_ws();
-            while matches!(
-                self.current(),
-                Some(TokenKind::BangEqual | TokenKind::EqualEqual)
-            ) {
-                self.bump();
-                self.comparison();
-            }
-            self.builder.finish_node();
+pub fn parse(tokens: Vec<lexer::Token>) -> Parse {
+    let mut p = Parser::new(tokens);
+    root(&mut p);
+
+    let mut builder = GreenNodeBuilder::new();
+    let mut errors = event::process(&mut builder, p.finish());
+
+    let green_node = builder.finish();
+    errors.extend(validation::validate(&SyntaxNode::new_root(green_node.clone())));
+
+    Parse { green_node, errors }
+}
+
+pub type SyntaxNode = rowan::SyntaxNode<Lang>;
+pub type SyntaxToken = rowan::SyntaxToken<Lang>;
+#[allow(unused)]
+pub type SyntaxElement = rowan::NodeOrToken<SyntaxNode, SyntaxToken>;
+
+impl Parse {
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green_node.clone())
+    }
+}
+
+/// A single text replacement: delete `delete` and put `insert` in its place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub delete: rowan::TextRange,
+    pub insert: String,
+}
+
+fn splice(text: &str, edit: &TextEdit) -> String {
+    let mut text = text.to_string();
+    text.replace_range(
+        usize::from(edit.delete.start())..usize::from(edit.delete.end()),
+        &edit.insert,
+    );
+    text
+}
+
+/// Strip off `Equality`/`Comparison`/`Term`/`Factor` wrappers that don't
+/// contain an actual operator -- i.e. have exactly one child node -- down
+/// to the real node underneath. These four levels always wrap an
+/// expression regardless of whether an operator at that level is present,
+/// so a bare `ParenExpr` at the top of a sub-parse still shows up four
+/// layers deep.
+fn unwrap_precedence_wrappers(mut node: SyntaxNode) -> SyntaxNode {
+    while matches!(
+        node.kind(),
+        SyntaxKind::Equality | SyntaxKind::Comparison | SyntaxKind::Term | SyntaxKind::Factor
+    ) {
+        let mut children = node.children();
+        let Some(only_child) = children.next() else {
+            break;
+        };
+        if children.next().is_some() {
+            break;
         }
+        node = only_child;
+    }
+    node
+}
+
+impl Parse {
+    /// Reparse after a single text edit without redoing the whole lex/parse
+    /// pass, modeled on rust-analyzer's `reparsing.rs`. Tries progressively
+    /// coarser-grained reparses, each reusing every untouched subtree by Arc
+    /// identity, and only falls back to [`parse`] of the whole document when
+    /// nothing smaller applies.
+    pub fn reparse(&self, edit: &TextEdit) -> Parse {
+        self.reparse_token(edit)
+            .or_else(|| self.reparse_block(edit))
+            .unwrap_or_else(|| self.reparse_full(edit))
+    }
 
-        fn expression(&mut self) {
-            self.skip_ws();
-            self.equality();
+    /// Re-lex just the single leaf token containing the edit. Succeeds only
+    /// if the edited token text re-lexes into exactly one token of the same
+    /// `SyntaxKind`, with nothing left over.
+    fn reparse_token(&self, edit: &TextEdit) -> Option<Parse> {
+        let root = self.syntax();
+        let token = root.token_at_offset(edit.delete.start()).right_biased()?;
+        if !token.text_range().contains_range(edit.delete) {
+            return None;
+        }
+        // Whitespace/newline runs can grow into (or shrink away from) their
+        // neighbours, so re-lexing them in isolation isn't trustworthy.
+        let old_kind = token.kind();
+        if matches!(old_kind, SyntaxKind::Whitespace | SyntaxKind::Newline) {
+            return None;
         }
 
-        fn parse(mut self) -> Parse {
-            self.builder.start_node(SyntaxKind::Root.into());
-            self.expression();
-            self.skip_ws();
+        let range_in_token = edit.delete - token.text_range().start();
+        let new_text = splice(
+            token.text(),
+            &TextEdit {
+                delete: range_in_token,
+                insert: edit.insert.clone(),
+            },
+        );
+
+        let relexed = lexer::lex(&new_text);
+        let [single] = relexed.as_slice() else {
+            return None;
+        };
+        if SyntaxKind::from(single.kind()) != old_kind {
+            return None;
+        }
 
-            while self.current() == Some(TokenKind::Newline) {
-                self.bump();
-            }
+        let new_token = rowan::GreenToken::new(old_kind.into(), &new_text);
+        let new_root = token.replace_with(new_token);
+        Some(Parse {
+            green_node: new_root,
+            errors: self.errors.clone(),
+        })
+    }
 
-            if self.current().is_some() {
-                self.builder.start_node(SyntaxKind::ErrorUnexpected.into());
-                while self.current().is_some() {
-                    self.bump()
-                }
-                self.errors.push("Expected EOF".to_string());
-                self.builder.finish_node();
-            }
-            self.builder.finish_node();
+    /// Walk up from the edit to the smallest ancestor node that is an
+    /// independently re-parsable unit (currently just `ParenExpr`), re-run
+    /// the parser over that node's text alone, and splice in only that
+    /// subtree.
+    ///
+    /// The re-parse's root always wraps its expression in all four
+    /// precedence levels (`Equality(Comparison(Term(Factor(...))))`), even
+    /// when there's no operator to justify them, so the node worth
+    /// splicing in is found by stripping those wrappers off first -- see
+    /// [`unwrap_precedence_wrappers`].
+    fn reparse_block(&self, edit: &TextEdit) -> Option<Parse> {
+        let root = self.syntax();
+        // An edit fully inside a token's range covers that token exactly,
+        // not its parent node, so start the climb from the token's parent
+        // rather than bailing out via `into_node()`.
+        let mut node = match root.covering_element(edit.delete) {
+            rowan::NodeOrToken::Node(node) => node,
+            rowan::NodeOrToken::Token(token) => token.parent()?,
+        };
+        while node.kind() != SyntaxKind::ParenExpr {
+            node = node.parent()?;
+        }
 
-            Parse {
-                green_node: self.builder.finish(),
-                errors: self.errors,
-            }
+        let range_in_node = edit.delete - node.text_range().start();
+        let new_text = splice(
+            &node.text().to_string(),
+            &TextEdit {
+                delete: range_in_node,
+                insert: edit.insert.clone(),
+            },
+        );
+
+        let sub_parse = parse(lexer::lex(&new_text));
+        if !sub_parse.errors.is_empty() {
+            return None;
+        }
+        let mut replacement = sub_parse.syntax().children();
+        let replacement = replacement.next()?;
+        if replacement.next_sibling().is_some() {
+            return None;
+        }
+        let unwrapped = unwrap_precedence_wrappers(replacement);
+        if unwrapped.kind() != SyntaxKind::ParenExpr {
+            return None;
         }
+
+        let new_root = node.replace_with(unwrapped.green().into());
+        Some(Parse {
+            green_node: new_root,
+            errors: self.errors.clone(),
+        })
     }
 
-    tokens.reverse();
-    Parser {
-        tokens,
-        builder: GreenNodeBuilder::new(),
-        errors: Vec::new(),
+    fn reparse_full(&self, edit: &TextEdit) -> Parse {
+        let new_text = splice(&self.syntax().text().to_string(), edit);
+        parse(lexer::lex(&new_text))
     }
-    .parse()
 }
 
-type SyntaxNode = rowan::SyntaxNode<Lang>;
-#[allow(unused)]
-type SyntaxToken = rowan::SyntaxToken<Lang>;
-#[allow(unused)]
-type SyntaxElement = rowan::NodeOrToken<SyntaxNode, SyntaxToken>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bin_expr_chain_nests_left_associatively() {
+        // `1+2+3` should come out as `((1+2)+3)` -- a `Term` wrapping a
+        // nested `Term` (the `1+2`) and a single trailing `Factor` operand
+        // (the `3`) -- not one flat `Term` with three operands and two
+        // operators.
+        let root = parse(lexer::lex("1+2+3")).syntax();
+        let outer = root
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::Term)
+            .expect("should find a Term node");
+        let children: Vec<_> = outer.children().collect();
+        assert_eq!(children.len(), 2, "outer Term should wrap its nested lhs plus one rhs operand");
+        assert_eq!(children[0].kind(), SyntaxKind::Term, "lhs should be the re-nested 1+2");
+
+        let inner_children: Vec<_> = children[0].children().collect();
+        assert_eq!(inner_children.len(), 2, "the re-nested 1+2 should itself wrap lhs plus rhs");
+        assert_eq!(inner_children[0].kind(), SyntaxKind::Term, "1 is wrapped once more, with no operator");
+        assert_eq!(inner_children[1].kind(), SyntaxKind::Factor, "rhs operand 2 stays a bare Factor");
+    }
 
-impl Parse {
-    pub fn syntax(&self) -> SyntaxNode {
-        SyntaxNode::new_root(self.green_node.clone())
+    #[test]
+    fn reparse_block_reuses_the_paren_expr_subtree() {
+        // Changing `+` to `-` re-lexes to a different `TokenKind`, so
+        // `reparse_token` can't apply and this has to go through
+        // `reparse_block`. Before this fix `reparse_block` could never
+        // succeed here: its sub-parse's top node is always `Equality`,
+        // never `ParenExpr`, and `covering_element` for an edit fully
+        // inside a token returns that token rather than its parent node,
+        // so this always fell through to a full reparse instead.
+        let parsed = parse(lexer::lex("(1+22)"));
+        let edit = TextEdit {
+            delete: rowan::TextRange::new(2.into(), 3.into()),
+            insert: "-".to_string(),
+        };
+        assert!(parsed.reparse_token(&edit).is_none(), "a `+`/`-` edit should not be a same-kind token reparse");
+
+        let reparsed = parsed
+            .reparse_block(&edit)
+            .expect("reparse_block should handle an edit fully contained in a ParenExpr");
+        assert_eq!(reparsed.syntax().text().to_string(), "(1-22)");
+    }
+
+    #[test]
+    fn reparse_token_splices_an_edit_within_a_single_number_token() {
+        // Editing one digit inside a `Number` re-lexes to exactly one
+        // token of the same kind, so this should take the cheap
+        // token-level splice path rather than falling through.
+        let parsed = parse(lexer::lex("12+3"));
+        let edit = TextEdit {
+            delete: rowan::TextRange::new(1.into(), 2.into()),
+            insert: "9".to_string(),
+        };
+        let reparsed = parsed
+            .reparse_token(&edit)
+            .expect("a same-kind digit edit should splice at the token level");
+        assert_eq!(reparsed.syntax().text().to_string(), "19+3");
+
+        let number = reparsed
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|t| t.kind() == SyntaxKind::Number)
+            .expect("should still contain a Number token");
+        assert_eq!(number.text(), "19");
     }
 }
@@ -1,84 +1,32 @@
-// allows the CST to extend the lex tokens
-macro_rules! build_impls {
-    ($v:tt, $($values:tt),*) => {
-        #[derive(Clone, Debug, Copy, PartialEq, Eq)]
-        pub enum TokenKind {
-            $v,
-            $($values),*
-        }
-
-        #[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-        #[repr(u16)]
-        pub enum SyntaxKind {
-            $v = 0, // fix variant will be zero
-            $($values),*,
+// `TokenKind`, `SyntaxKind`, `impl From<TokenKind> for SyntaxKind`, and the
+// keyword `KEYWORDS` map all come from `grammar.ron` via `build.rs`, so
+// adding a keyword or a node kind is a one-line grammar edit instead of a
+// hand-maintained enum plus a hand-maintained `phf` map that can drift out
+// of lockstep. See `codegen.rs` for the generator.
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+/// A diagnostic collected during parsing or validation. `range` is `None`
+/// for diagnostics raised while the `TextRange` of the offending token
+/// isn't readily at hand (e.g. mid-recovery); passes that walk the finished
+/// tree, like `validation`, always set it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub range: Option<rowan::TextRange>,
+}
 
-            Root // root must be last; it is used for bounds checking
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Diagnostic {
+            message: message.into(),
+            range: None,
         }
+    }
 
-        impl From<TokenKind> for SyntaxKind {
-            fn from(value: TokenKind) -> Self {
-                match value {
-                    TokenKind::$v => SyntaxKind::$v,
-                    $( TokenKind::$values => SyntaxKind::$values ),*
-                }
-            }
+    pub fn at(message: impl Into<String>, range: rowan::TextRange) -> Self {
+        Diagnostic {
+            message: message.into(),
+            range: Some(range),
         }
-    };
-}
-
-build_impls! {
-    // single character
-    LParen,
-    RParen,
-    LBrac,
-    RBrac,
-    Comma,
-    Dot,
-    Minus,
-    Plus,
-    Semicolon,
-    Slash,
-    Star,
-
-    // one or two character
-    Bang,
-    BangEqual,
-    Equal,
-    EqualEqual,
-    Greater,
-    GreaterEqual,
-    Less,
-    LessEqual,
-
-    // Literals
-    Identifier,
-    StringLiteral, // don't clobber builtin "String"
-    Number,
-
-    // Keywords
-    And,
-    Class,
-    Else,
-    False,
-    Fn,
-    For,
-    If,
-    Nil,
-    Or,
-    Print,
-    Return,
-    Super,
-    This,
-    True,
-    Var,
-    While,
-
-    LineComment,
-    BlockComment,
-    Whitespace,
-    Newline,
-
-    ErrorUnexpected,
-    ErrorUnterminatedString
+    }
 }
@@ -0,0 +1,320 @@
+//! Post-parse diagnostics that the grammar itself can't raise, in the spirit
+//! of rust-analyzer's `validation/string.rs`. The lexer deliberately keeps
+//! token text a byte-for-byte copy of the source and doesn't judge whether
+//! an escape or a numeric literal actually makes sense; this module does
+//! that judging, and doubles as the place that decodes a literal's runtime
+//! value once it's known to be valid.
+
+use rowan::{TextRange, TextSize};
+
+use crate::ast::{AstNode, Literal, LiteralKind};
+use crate::parser::SyntaxNode;
+use crate::types::Diagnostic;
+
+/// Walk every node in the tree and validate the literals that need it.
+pub fn validate(root: &SyntaxNode) -> Vec<Diagnostic> {
+    root.descendants()
+        .filter_map(Literal::cast)
+        .flat_map(|literal| match literal.kind() {
+            LiteralKind::String => validate_string(&literal),
+            LiteralKind::Number => validate_number(&literal),
+            LiteralKind::Bool(_) | LiteralKind::Nil => Vec::new(),
+        })
+        .collect()
+}
+
+/// Decode a `StringLiteral`'s escapes into its runtime value. Malformed
+/// escapes decode to nothing; call [`validate`] first to surface those.
+pub fn decode_string_literal(literal: &Literal) -> String {
+    decode_body(&body(literal)).0
+}
+
+/// The token text with the surrounding quotes stripped; tolerates a missing
+/// closing quote (an unterminated string still has a body worth decoding).
+/// Owned because the token it's sliced from is itself a temporary.
+fn body(literal: &Literal) -> String {
+    let text = literal.token().text().to_string();
+    let end = if text.len() > 1 && text.ends_with('"') {
+        text.len() - 1
+    } else {
+        text.len()
+    };
+    text[1..end].to_string()
+}
+
+fn validate_string(literal: &Literal) -> Vec<Diagnostic> {
+    let token = literal.token();
+    let base = token.text_range().start() + TextSize::from(1);
+    let (_, errors) = decode_body(&body(literal));
+
+    errors
+        .into_iter()
+        .map(|(range, message)| {
+            let range = TextRange::new(
+                base + TextSize::try_from(range.start).unwrap(),
+                base + TextSize::try_from(range.end).unwrap(),
+            );
+            Diagnostic::at(message, range)
+        })
+        .collect()
+}
+
+/// Decode the escapes in `body` (quotes already stripped), returning the
+/// runtime string value plus one `(byte range in body, message)` pair per
+/// malformed escape.
+fn decode_body(body: &str) -> (String, Vec<(std::ops::Range<usize>, String)>) {
+    let mut value = String::new();
+    let mut errors = Vec::new();
+    let mut i = 0;
+
+    while i < body.len() {
+        let c = body[i..].chars().next().unwrap();
+        if c != '\\' {
+            value.push(c);
+            i += c.len_utf8();
+            continue;
+        }
+
+        let escape_start = i;
+        let Some(kind) = body[i + 1..].chars().next() else {
+            errors.push((escape_start..body.len(), "unterminated escape sequence".to_string()));
+            break;
+        };
+
+        match kind {
+            '\\' => {
+                value.push('\\');
+                i += 2;
+            }
+            '"' => {
+                value.push('"');
+                i += 2;
+            }
+            'n' => {
+                value.push('\n');
+                i += 2;
+            }
+            't' => {
+                value.push('\t');
+                i += 2;
+            }
+            'r' => {
+                value.push('\r');
+                i += 2;
+            }
+            '0' => {
+                value.push('\0');
+                i += 2;
+            }
+            'x' => {
+                let hex = body.get(i + 2..i + 4).filter(|h| h.len() == 2);
+                match hex.filter(|h| h.chars().all(|c| c.is_ascii_hexdigit())) {
+                    Some(hex) => {
+                        value.push(u8::from_str_radix(hex, 16).unwrap() as char);
+                        i += 4;
+                    }
+                    None => {
+                        let end = body[i + 2..]
+                            .char_indices()
+                            .nth(2)
+                            .map_or(body.len(), |(offset, _)| i + 2 + offset);
+                        errors.push((escape_start..end, "`\\x` needs two hex digits".to_string()));
+                        i = end;
+                    }
+                }
+            }
+            'u' => {
+                if body[i + 2..].starts_with('{') {
+                    match body[i + 2..].find('}') {
+                        Some(offset) => {
+                            let close = i + 2 + offset;
+                            let hex = &body[i + 3..close];
+                            let valid_len = !hex.is_empty() && hex.len() <= 6;
+                            let valid_digits = hex.chars().all(|c| c.is_ascii_hexdigit());
+                            if valid_len && valid_digits {
+                                match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                                    Some(decoded) => value.push(decoded),
+                                    None => errors.push((
+                                        escape_start..close + 1,
+                                        "invalid unicode scalar value".to_string(),
+                                    )),
+                                }
+                            } else {
+                                errors.push((
+                                    escape_start..close + 1,
+                                    "invalid unicode escape".to_string(),
+                                ));
+                            }
+                            i = close + 1;
+                        }
+                        None => {
+                            errors.push((
+                                escape_start..body.len(),
+                                "unterminated unicode escape".to_string(),
+                            ));
+                            i = body.len();
+                        }
+                    }
+                } else {
+                    errors.push((escape_start..i + 2, "expected `{` after `\\u`".to_string()));
+                    i += 2;
+                }
+            }
+            other => {
+                let end = i + 1 + other.len_utf8();
+                errors.push((escape_start..end, format!("unknown escape `\\{other}`")));
+                i = end;
+            }
+        }
+    }
+
+    (value, errors)
+}
+
+/// The decoded value of a `Number` literal: hex/binary/octal literals and
+/// bare integers decode to `Int`, anything with a `.` or an exponent
+/// decodes to `Float`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+/// Decode a `Number` literal's runtime value. Literals whose digits fail to
+/// parse decode to `Int(0)`/`Float(0.0)`; a literal with only a trailing `_`
+/// wrong still decodes its digits (`1_` is `Int(1)`). Call [`validate`]
+/// first to surface either.
+pub fn decode_number_literal(literal: &Literal) -> Number {
+    decode_number_text(literal.token().text()).0
+}
+
+fn validate_number(literal: &Literal) -> Vec<Diagnostic> {
+    let token = literal.token();
+    let base = token.text_range().start();
+    let (_, errors) = decode_number_text(token.text());
+
+    errors
+        .into_iter()
+        .map(|(range, message)| {
+            let range = TextRange::new(
+                base + TextSize::try_from(range.start).unwrap(),
+                base + TextSize::try_from(range.end).unwrap(),
+            );
+            Diagnostic::at(message, range)
+        })
+        .collect()
+}
+
+fn strip_underscores(text: &str) -> String {
+    text.chars().filter(|c| *c != '_').collect()
+}
+
+fn decode_number_text(text: &str) -> (Number, Vec<(std::ops::Range<usize>, String)>) {
+    for (prefix, radix) in [("0x", 16), ("0X", 16), ("0b", 2), ("0B", 2), ("0o", 8), ("0O", 8)] {
+        if let Some(digits) = text.strip_prefix(prefix) {
+            return decode_radix_int(digits, radix, text.len());
+        }
+    }
+
+    let mut errors = Vec::new();
+    if text.matches('.').count() > 1 {
+        errors.push((0..text.len(), "number literal has more than one `.`".to_string()));
+        return (Number::Float(0.0), errors);
+    }
+    if text.ends_with('_') {
+        errors.push((text.len() - 1..text.len(), "number literal cannot end with `_`".to_string()));
+    }
+
+    let cleaned = strip_underscores(text);
+    let is_float = cleaned.contains(['.', 'e', 'E']);
+    if is_float {
+        match cleaned.parse::<f64>() {
+            Ok(value) => (Number::Float(value), errors),
+            Err(_) => {
+                errors.push((0..text.len(), "invalid floating point literal".to_string()));
+                (Number::Float(0.0), errors)
+            }
+        }
+    } else {
+        match cleaned.parse::<i64>() {
+            Ok(value) => (Number::Int(value), errors),
+            Err(_) => {
+                errors.push((0..text.len(), "invalid integer literal".to_string()));
+                (Number::Int(0), errors)
+            }
+        }
+    }
+}
+
+fn decode_radix_int(
+    digits: &str,
+    radix: u32,
+    full_len: usize,
+) -> (Number, Vec<(std::ops::Range<usize>, String)>) {
+    let mut errors = Vec::new();
+    let cleaned = strip_underscores(digits);
+    if cleaned.is_empty() {
+        errors.push((0..full_len, "expected digits after radix prefix".to_string()));
+        return (Number::Int(0), errors);
+    }
+    if digits.ends_with('_') {
+        errors.push((full_len - 1..full_len, "number literal cannot end with `_`".to_string()));
+    }
+    match i64::from_str_radix(&cleaned, radix) {
+        Ok(value) => (Number::Int(value), errors),
+        Err(_) => {
+            errors.push((0..full_len, "invalid digits for this radix".to_string()));
+            (Number::Int(0), errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AstNode;
+    use crate::parser;
+
+    fn first_literal(src: &str) -> Literal {
+        let root = parser::parse(crate::lexer::lex(src)).syntax();
+        root.descendants().find_map(Literal::cast).expect("source should contain a literal")
+    }
+
+    #[test]
+    fn decodes_escapes_in_a_string_literal() {
+        let literal = first_literal(r#""a\tb\n""#);
+        assert_eq!(decode_string_literal(&literal), "a\tb\n");
+    }
+
+    #[test]
+    fn reports_an_unknown_escape() {
+        let literal = first_literal(r#""\q""#);
+        let errors = validate_string(&literal);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("unknown escape"));
+    }
+
+    #[test]
+    fn reports_a_number_literal_with_more_than_one_dot() {
+        // Needs the lexer to have kept `1.2.3` as a single `Number` token
+        // in the first place for this diagnostic to ever see it.
+        let literal = first_literal("1.2.3");
+        let errors = validate_number(&literal);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("more than one `.`"));
+    }
+
+    #[test]
+    fn decodes_a_hex_literal() {
+        let literal = first_literal("0xFF");
+        assert_eq!(decode_number_literal(&literal), Number::Int(255));
+    }
+
+    #[test]
+    fn reports_a_trailing_underscore_in_a_hex_literal() {
+        let literal = first_literal("0x1_");
+        let errors = validate_number(&literal);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("cannot end with `_`"));
+    }
+}